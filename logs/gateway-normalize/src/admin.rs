@@ -0,0 +1,56 @@
+//! A small admin HTTP server exposing `/metrics` in Prometheus text format,
+//! mirroring how the storage/admin services ship a dedicated metrics route.
+
+use crate::metrics::Metrics;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use slog::Logger;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+/// Serves `/metrics` on `addr` until the process exits.
+/// Any other path returns a `404`.
+pub async fn serve(addr: SocketAddr, metrics: Arc<Metrics>, logger: Logger) -> anyhow::Result<()> {
+    let make_service = make_service_fn(move |_conn| {
+        let metrics = Arc::clone(&metrics);
+        let logger = logger.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                handle(req, Arc::clone(&metrics), logger.clone())
+            }))
+        }
+    });
+
+    slog::info!(logger, "starting admin metrics server"; "addr" => %addr);
+    Server::bind(&addr).serve(make_service).await?;
+    Ok(())
+}
+
+async fn handle(
+    req: Request<Body>,
+    metrics: Arc<Metrics>,
+    logger: Logger,
+) -> Result<Response<Body>, Infallible> {
+    if req.uri().path() != "/metrics" {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::empty())
+            .unwrap());
+    }
+
+    match metrics.render() {
+        Ok(body) => Ok(Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "text/plain; version=0.0.4")
+            .body(Body::from(body))
+            .unwrap()),
+        Err(err) => {
+            slog::error!(logger, "failed to render metrics"; "error" => ?err);
+            Ok(Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(Body::empty())
+                .unwrap())
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Emits a standardized, versioned audit record for every normalized event,
+//! decoupled from the free-form service logs. Sits as a thin layer around
+//! `ProcessorFleet::normalize`: once a `NormalizedEvent` (or a
+//! `ProcessingError`) is produced, it's projected into this schema and
+//! flushed to a pluggable sink before the normalization result is
+//! considered complete, giving a machine-parseable trail. Records are written
+//! as plain JSON lines with no hash chain or signature, so the trail is not
+//! tamper-evident.
+
+use crate::event::NormalizedEvent;
+use crate::gateway::ProcessingError;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncWriteExt, BufWriter};
+use tokio::sync::Mutex;
+
+/// Bumped whenever the shape of `AuditRecord` changes in a way downstream
+/// consumers need to know about
+const AUDIT_SCHEMA_VERSION: u32 = 1;
+
+/// The outcome of normalizing a single gateway event
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum AuditOutcome {
+    Normalized,
+    Dropped,
+    Failed { reason: String },
+}
+
+/// A stable, versioned projection of a normalization attempt,
+/// independent of the internal `NormalizedEvent`/`ProcessingError` shapes.
+/// `actor`/`target` are each event type's `Agent`/`Entity` run through
+/// `serde_json::to_value`, not a `Debug` dump, so the record only depends on
+/// those types' serde field contract rather than incidental derived
+/// formatting.
+#[derive(Clone, Debug, Serialize)]
+pub struct AuditRecord {
+    pub schema_version: u32,
+    /// Carried over from the originating gateway event's id,
+    /// so this record can be correlated back to it
+    pub request_id: String,
+    pub guild_id: u64,
+    pub timestamp: u64,
+    pub actor: Option<serde_json::Value>,
+    pub action: String,
+    pub target: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub outcome: AuditOutcome,
+}
+
+/// Projects a normalization attempt into the stable audit schema
+#[must_use]
+pub fn project(
+    request_id: String,
+    guild_id: u64,
+    fallback_timestamp: u64,
+    event_type: &str,
+    result: &Result<NormalizedEvent, ProcessingError>,
+) -> AuditRecord {
+    match result {
+        Ok(normalized) => AuditRecord {
+            schema_version: AUDIT_SCHEMA_VERSION,
+            request_id,
+            guild_id,
+            timestamp: normalized.timestamp,
+            actor: normalized
+                .agent
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .unwrap_or_default(),
+            action: event_type.to_string(),
+            target: normalized
+                .subject
+                .as_ref()
+                .map(serde_json::to_value)
+                .transpose()
+                .unwrap_or_default(),
+            outcome: AuditOutcome::Normalized,
+        },
+        Err(ProcessingError::Drop) => AuditRecord {
+            schema_version: AUDIT_SCHEMA_VERSION,
+            request_id,
+            guild_id,
+            timestamp: fallback_timestamp,
+            actor: None,
+            action: event_type.to_string(),
+            target: None,
+            outcome: AuditOutcome::Dropped,
+        },
+        Err(err) => AuditRecord {
+            schema_version: AUDIT_SCHEMA_VERSION,
+            request_id,
+            guild_id,
+            timestamp: fallback_timestamp,
+            actor: None,
+            action: event_type.to_string(),
+            target: None,
+            outcome: AuditOutcome::Failed {
+                reason: err.to_string(),
+            },
+        },
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuditSinkError {
+    #[error("failed to serialize the audit record")]
+    Serialization(#[source] serde_json::Error),
+    #[error("failed to write the audit record")]
+    Io(#[source] std::io::Error),
+}
+
+/// A destination for projected audit records.
+/// Implementations must flush before `write` returns so that a caller
+/// awaiting it can treat the record as durably recorded.
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn write(&self, record: &AuditRecord) -> Result<(), AuditSinkError>;
+}
+
+/// Appends each record as a line of JSON to a file, flushing after every write
+pub struct JsonLinesFileSink {
+    writer: Mutex<BufWriter<tokio::fs::File>>,
+}
+
+impl JsonLinesFileSink {
+    pub async fn open(path: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path.into())
+            .await?;
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+}
+
+#[async_trait]
+impl AuditSink for JsonLinesFileSink {
+    async fn write(&self, record: &AuditRecord) -> Result<(), AuditSinkError> {
+        let mut line = serde_json::to_vec(record).map_err(AuditSinkError::Serialization)?;
+        line.push(b'\n');
+
+        let mut writer = self.writer.lock().await;
+        writer.write_all(&line).await.map_err(AuditSinkError::Io)?;
+        writer.flush().await.map_err(AuditSinkError::Io)?;
+        Ok(())
+    }
+}
+
+/// A sink that discards every record, used when audit emission isn't configured
+pub struct NoopSink;
+
+#[async_trait]
+impl AuditSink for NoopSink {
+    async fn write(&self, _record: &AuditRecord) -> Result<(), AuditSinkError> {
+        Ok(())
+    }
+}
+
+/// Convenience alias for the shared, dynamically-dispatched sink
+/// `ProcessorFleet` holds, so new sink implementations can be swapped in
+/// without changing its signature
+pub type SharedAuditSink = Arc<dyn AuditSink>;
@@ -0,0 +1,58 @@
+//! Runtime configuration for the gateway-normalize service, loaded once at
+//! startup from the YAML file given on the command line and threaded through
+//! the rest of the service as an `Arc<Configuration>`.
+
+use crate::gateway::AuditLogRetryConfig;
+use serde::Deserialize;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+/// Top-level configuration for the service
+#[derive(Deserialize, Debug, Clone)]
+pub struct Configuration {
+    /// Bot token used to authenticate the Twilight HTTP client
+    pub discord_token: String,
+    /// Selects the transport `ingestion::from_config` builds an `IngestionSource` from
+    #[serde(default)]
+    pub ingestion: IngestionConfig,
+    /// Address the admin metrics server (see `admin::serve`) binds to
+    pub admin_addr: SocketAddr,
+    /// Where `JsonLinesFileSink` appends projected audit records
+    pub audit_log_path: PathBuf,
+    /// Governs `Context::get_audit_log_entry`'s retry loop. Falls back to
+    /// `AuditLogRetryConfig::default` if the `audit_log_retry` section is
+    /// omitted from the config file.
+    #[serde(default)]
+    pub audit_log_retry: AuditLogRetryConfig,
+}
+
+impl Configuration {
+    /// Loads and parses the configuration file at `path`
+    pub fn try_load(path: &str) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&contents)?)
+    }
+}
+
+/// Selects which transport `ingestion::from_config` constructs an
+/// `IngestionSource` from
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum IngestionConfig {
+    /// The existing bespoke gateway queue, fed via an in-process channel
+    Queue,
+    /// A Redis stream, consumed via a consumer group so multiple normalizer
+    /// replicas can share the load with at-least-once delivery
+    Redis {
+        url: String,
+        stream_key: String,
+        consumer_group: String,
+        consumer_name: String,
+    },
+}
+
+impl Default for IngestionConfig {
+    fn default() -> Self {
+        Self::Queue
+    }
+}
@@ -15,11 +15,14 @@ use crate::{audit_log, util};
 use anyhow::Context as _;
 use futures::try_join;
 use jmespath::Variable;
+use rand::Rng;
+use serde::Deserialize;
 use slog::Logger;
 use static_assertions::assert_impl_all;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use twilight_http::Client;
@@ -59,13 +62,6 @@ pub enum ProcessingError {
     NoAuditLogEntry(String),
 }
 
-impl ProcessingError {
-    /// Whether the error occurs in a non-nominal case that should be logged
-    pub const fn is_unexpected(&self) -> bool {
-        !matches!(self, Self::Drop)
-    }
-}
-
 /// Represents a collection of processors that each have
 /// a corresponding gateway event type
 /// and are capable of normalizing raw JSON of that type
@@ -76,6 +72,9 @@ pub struct ProcessorFleet {
     config: Arc<Configuration>,
     emojis: Arc<crate::emoji::Db>,
     logger: Logger,
+    metrics: Arc<crate::metrics::Metrics>,
+    permissions: Arc<crate::permissions::PermissionResolver>,
+    audit_sink: crate::audit_emit::SharedAuditSink,
 }
 
 // ProcessorFleet needs to be safe to share
@@ -89,6 +88,9 @@ impl ProcessorFleet {
         config: Arc<Configuration>,
         emojis: Arc<crate::emoji::Db>,
         logger: Logger,
+        metrics: Arc<crate::metrics::Metrics>,
+        permissions: Arc<crate::permissions::PermissionResolver>,
+        audit_sink: crate::audit_emit::SharedAuditSink,
     ) -> Self {
         Self {
             processors: HashMap::new(),
@@ -96,6 +98,9 @@ impl ProcessorFleet {
             config,
             emojis,
             logger,
+            metrics,
+            permissions,
+            audit_sink,
         }
     }
 
@@ -112,7 +117,26 @@ impl ProcessorFleet {
         &self,
         event: EventWithSource,
     ) -> Result<NormalizedEvent, ProcessingError> {
-        if let Some(processor) = self.processors.get(&event.inner.event_type) {
+        let event_type = event.inner.event_type.clone();
+        let request_id = event.inner.id.clone();
+        let guild_id = event.inner.guild_id;
+        let ingress_timestamp = event.inner.ingress_timestamp;
+        let start = std::time::Instant::now();
+
+        // A role's permissions (or the bot's own roles) may have just
+        // changed; drop the cached state eagerly rather than waiting out
+        // `PermissionResolver`'s TTL, so the next permission check in this
+        // guild reflects it immediately
+        if matches!(
+            event_type.as_str(),
+            "GUILD_ROLE_CREATE" | "GUILD_ROLE_UPDATE" | "GUILD_ROLE_DELETE" | "GUILD_MEMBER_UPDATE"
+        ) {
+            self.permissions
+                .invalidate(twilight_model::id::GuildId(guild_id))
+                .await;
+        }
+
+        let result = if let Some(processor) = self.processors.get(&event_type) {
             let logger = self.logger.new(slog::o!(
                 "event_id" => event.inner.id.clone(),
                 "event_ingress_timestamp" => event.inner.ingress_timestamp,
@@ -120,13 +144,38 @@ impl ProcessorFleet {
                 "event_guild_id" => event.inner.guild_id
             ));
             processor
-                .apply(event, &self.client, &self.config, &self.emojis, &logger)
+                .apply(
+                    event,
+                    &self.client,
+                    &self.config,
+                    &self.emojis,
+                    &logger,
+                    &self.metrics,
+                    &self.permissions,
+                )
                 .await
         } else {
-            Err(ProcessingError::SubProcessorNotFound(
-                event.inner.event_type,
-            ))
+            Err(ProcessingError::SubProcessorNotFound(event_type.clone()))
+        };
+
+        self.metrics
+            .record_processed(&event_type, start.elapsed(), &result);
+
+        // Project and flush the audit record before this result is
+        // considered complete, so the audit trail is never behind
+        // what callers have already observed
+        let audit_record = crate::audit_emit::project(
+            request_id,
+            guild_id,
+            ingress_timestamp,
+            &event_type,
+            &result,
+        );
+        if let Err(err) = self.audit_sink.write(&audit_record).await {
+            slog::error!(self.logger, "failed to emit audit record"; "error" => ?err);
         }
+
+        result
     }
 }
 
@@ -144,9 +193,15 @@ impl Processor {
         config: &'a Configuration,
         emojis: &'a crate::emoji::Db,
         logger: &'a Logger,
+        metrics: &'a crate::metrics::Metrics,
+        permissions: &'a crate::permissions::PermissionResolver,
     ) -> Result<NormalizedEvent, ProcessingError> {
         match self {
-            Self::Split(split) => split.apply(event, client, config, emojis, logger).await,
+            Self::Split(split) => {
+                split
+                    .apply(event, client, config, emojis, logger, metrics, permissions)
+                    .await
+            }
             Self::Direct(direct_source) => {
                 let EventWithSource {
                     inner: event,
@@ -161,6 +216,7 @@ impl Processor {
                     config,
                     emojis,
                     logger,
+                    permissions,
                 };
 
                 direct_source.consume(ctx).await
@@ -194,7 +250,14 @@ impl SplitProcessor {
         config: &'a Configuration,
         emojis: &'a crate::emoji::Db,
         logger: &'a Logger,
+        metrics: &'a crate::metrics::Metrics,
+        permissions: &'a crate::permissions::PermissionResolver,
     ) -> Result<NormalizedEvent, ProcessingError> {
+        // Held for the whole call, including time spent below waiting on
+        // `audit_log_lock`, so the gauge reflects actual in-flight `apply`
+        // calls rather than just the audit-log load within them
+        let _in_flight_guard = metrics.track_apply();
+
         let EventWithSource {
             inner: event,
             source,
@@ -210,6 +273,7 @@ impl SplitProcessor {
             config,
             emojis,
             logger,
+            permissions,
         };
 
         let write_lock = if self.audit_log.is_some() {
@@ -333,6 +397,7 @@ pub struct Context<'a> {
     config: &'a Configuration,
     emojis: &'a crate::emoji::Db,
     logger: &'a Logger,
+    permissions: &'a crate::permissions::PermissionResolver,
 }
 
 #[allow(dead_code)]
@@ -365,28 +430,102 @@ impl Context<'_> {
         path.extract(&audit_log_entry.json, &extractor, self.clone())
     }
 
-    /// Determines whether the Architus user has permissions in the guild for this event's context
-    pub async fn has_perms(&self, _permissions: Permissions) -> Result<bool, anyhow::Error> {
-        // TODO implement
-        Ok(true)
+    /// Determines whether the Architus user has permissions in the guild for
+    /// this event's context, optionally narrowed to a specific channel's
+    /// effective permission overwrites
+    pub async fn has_perms(
+        &self,
+        permissions: Permissions,
+        channel: Option<&Channel>,
+    ) -> Result<bool, anyhow::Error> {
+        let guild_id = twilight_model::id::GuildId(self.event.guild_id);
+        let channel_id = channel.map(|channel| twilight_model::id::ChannelId(channel.id));
+        self.permissions
+            .resolve(guild_id, channel_id, permissions)
+            .await
     }
 
-    /// Runs an audit log search on the guild for this event's context
+    /// Runs an audit log search on the guild for this event's context,
+    /// retrying with exponential backoff and full jitter until a match is
+    /// found or `config.audit_log_retry`'s deadline elapses. Discord audit
+    /// log entries are eventually consistent, so the entry for a just-received
+    /// gateway event is often not yet queryable on the first attempt.
     pub async fn get_audit_log_entry<P>(
         &self,
         search: SearchQuery<P>,
     ) -> Result<AuditLogEntry, anyhow::Error>
     where
-        P: Fn(&AuditLogEntry) -> bool,
+        P: Fn(&AuditLogEntry) -> bool + Clone,
     {
-        audit_log::get_entry(self.client, search)
-            .await
-            .with_context(|| {
-                format!(
-                    "audit log search failed for event type {}",
-                    self.event.event_type
-                )
-            })
+        let retry = &self.config.audit_log_retry;
+        let deadline = tokio::time::Instant::now() + retry.max_elapsed;
+        let mut delay = retry.initial_delay;
+
+        loop {
+            match audit_log::get_entry(self.client, search.clone()).await {
+                Ok(entry) => return Ok(entry),
+                Err(err) => {
+                    let now = tokio::time::Instant::now();
+                    if now >= deadline {
+                        return Err(err).with_context(|| {
+                            format!(
+                                "audit log search failed for event type {} after retrying for {:?}",
+                                self.event.event_type, retry.max_elapsed
+                            )
+                        });
+                    }
+
+                    // Full jitter: sleep a random duration in [0, delay),
+                    // capped so the final attempt still lands before the deadline
+                    let delay_ms = delay.as_millis().max(1) as u64;
+                    let jitter = rand::thread_rng().gen_range(0..delay_ms);
+                    let sleep_for = Duration::from_millis(jitter).min(deadline - now);
+                    tokio::time::sleep(sleep_for).await;
+
+                    delay = delay.mul_f64(retry.multiplier).min(retry.max_delay);
+                }
+            }
+        }
+    }
+}
+
+/// Governs the exponential-backoff retry loop in `Context::get_audit_log_entry`.
+/// Deserialized directly from the `audit_log_retry` section of the config
+/// file, with millisecond fields so it's plain to express in YAML.
+#[derive(Deserialize, Clone, Debug)]
+pub struct AuditLogRetryConfig {
+    /// The delay before the second attempt, in milliseconds; each subsequent
+    /// attempt's delay is the previous one scaled by `multiplier`
+    #[serde(rename = "initial_delay_ms", deserialize_with = "duration_millis")]
+    pub initial_delay: Duration,
+    /// How much the delay grows after each failed attempt
+    pub multiplier: f64,
+    /// The maximum per-attempt delay in milliseconds, capping the
+    /// exponential growth
+    #[serde(rename = "max_delay_ms", deserialize_with = "duration_millis")]
+    pub max_delay: Duration,
+    /// The total time budget across all attempts, in milliseconds, before
+    /// giving up and surfacing the most recent `NoAuditLogEntry`-style error
+    #[serde(rename = "max_elapsed_ms", deserialize_with = "duration_millis")]
+    pub max_elapsed: Duration,
+}
+
+/// Deserializes a plain millisecond count into a `Duration`
+fn duration_millis<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(Duration::from_millis(u64::deserialize(deserializer)?))
+}
+
+impl Default for AuditLogRetryConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_millis(200),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(2),
+            max_elapsed: Duration::from_secs(8),
+        }
     }
 }
 
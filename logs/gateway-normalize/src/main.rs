@@ -0,0 +1,113 @@
+#![warn(clippy::all, clippy::pedantic, clippy::nursery)]
+
+mod admin;
+mod audit_emit;
+mod audit_log;
+mod config;
+mod emoji;
+mod event;
+mod gateway;
+mod ingestion;
+mod metrics;
+mod permissions;
+mod rpc;
+mod util;
+
+use crate::config::Configuration;
+use crate::gateway::{ProcessingError, ProcessorFleet};
+use slog::{Drain, Logger};
+use std::sync::Arc;
+use twilight_http::Client;
+
+/// Loads the config and bootstraps the service
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let config_path = std::env::args().nth(1).expect(
+        "no config path given \
+        \nUsage: \
+        \ngateway-normalize [config-path]",
+    );
+    let config = Arc::new(Configuration::try_load(&config_path)?);
+    let logger = build_logger();
+
+    slog::info!(
+        logger,
+        "starting service";
+        "config_path" => config_path,
+    );
+
+    if let Err(err) = run(config, logger.clone()).await {
+        slog::error!(
+            logger,
+            "an error occurred during service execution";
+            "error" => ?err,
+        );
+    }
+    Ok(())
+}
+
+fn build_logger() -> Logger {
+    let decorator = slog_term::TermDecorator::new().build();
+    let drain = slog_term::FullFormat::new(decorator).build().fuse();
+    let drain = slog_async::Async::new(drain).build().fuse();
+    Logger::root(drain, slog::o!())
+}
+
+/// Wires up the processing pipeline: builds the shared state `ProcessorFleet`
+/// needs, selects and connects the configured ingestion source, then drains
+/// it until the source is exhausted or a fatal error occurs
+async fn run(config: Arc<Configuration>, logger: Logger) -> anyhow::Result<()> {
+    let client = Client::new(config.discord_token.clone());
+    let emojis = Arc::new(emoji::Db::new());
+    let metrics = Arc::new(metrics::Metrics::new()?);
+    // `Client` is cheaply `Clone` and shares its rate-limiter across clones,
+    // so permission-check traffic stays accounted for against the same
+    // per-route/global limits as event-processing traffic, rather than a
+    // second client racing it with its own independent bookkeeping
+    let permissions = Arc::new(permissions::PermissionResolver::new(client.clone()));
+    let audit_sink: audit_emit::SharedAuditSink =
+        Arc::new(audit_emit::JsonLinesFileSink::open(&config.audit_log_path).await?);
+
+    let fleet = Arc::new(ProcessorFleet::new(
+        client,
+        Arc::clone(&config),
+        emojis,
+        logger.clone(),
+        Arc::clone(&metrics),
+        permissions,
+        audit_sink,
+    ));
+
+    // Serve `/metrics` for as long as the process runs; a failure here
+    // shouldn't take down event processing, so it's only logged
+    let admin_logger = logger.clone();
+    let admin_metrics = Arc::clone(&metrics);
+    let admin_addr = config.admin_addr;
+    tokio::spawn(async move {
+        if let Err(err) = admin::serve(admin_addr, admin_metrics, admin_logger.clone()).await {
+            slog::error!(admin_logger, "admin metrics server exited"; "error" => ?err);
+        }
+    });
+
+    // Nothing in this binary sends into `queue_sender` yet: the bespoke
+    // gateway queue's websocket consumer lives outside this crate and isn't
+    // wired up. `from_config` rejects `IngestionConfig::Queue` until it is,
+    // rather than handing back a source whose `recv` would block forever.
+    let (_queue_sender, queue_receiver) = tokio::sync::mpsc::channel(1024);
+    let mut source = ingestion::from_config(&config, queue_receiver, logger.clone()).await?;
+
+    while let Some(event) = source.recv().await? {
+        let result = fleet.normalize(event).await;
+        match &result {
+            // `Drop` means a processor deliberately discarded the event, not
+            // that processing failed, so it's settled the same as success
+            Ok(_) | Err(ProcessingError::Drop) => source.ack().await?,
+            Err(err) => {
+                slog::warn!(logger, "failed to normalize event"; "error" => ?err);
+                source.nack().await?;
+            }
+        }
+    }
+
+    Ok(())
+}
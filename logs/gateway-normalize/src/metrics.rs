@@ -0,0 +1,120 @@
+//! Per-event-type metrics for `ProcessorFleet`, exposed over an admin HTTP
+//! endpoint in Prometheus text format, mirroring how the storage/admin
+//! services ship a dedicated metrics route.
+
+use crate::gateway::ProcessingError;
+use prometheus::{Encoder, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder};
+
+/// The label used on the per-variant error counter for each `ProcessingError` case
+fn error_variant_label(error: &ProcessingError) -> &'static str {
+    match error {
+        ProcessingError::SubProcessorNotFound(_) => "sub_processor_not_found",
+        ProcessingError::FatalSourceError(_) => "fatal_source_error",
+        ProcessingError::Drop => "drop",
+        ProcessingError::NoAuditLogEntry(_) => "no_audit_log_entry",
+    }
+}
+
+/// Holds the Prometheus collectors tracking `ProcessorFleet` activity.
+/// Cheaply cloneable; intended to be shared behind an `Arc`.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    processed_total: IntCounterVec,
+    errors_total: IntCounterVec,
+    processing_duration_seconds: HistogramVec,
+    in_flight_apply_calls: IntGauge,
+}
+
+impl Metrics {
+    /// Builds a fresh set of collectors, registered to their own registry
+    /// so the admin endpoint can render just these metrics
+    pub fn new() -> anyhow::Result<Self> {
+        let registry = Registry::new();
+
+        let processed_total = IntCounterVec::new(
+            Opts::new(
+                "gateway_normalize_processed_total",
+                "Total gateway events processed, by event type",
+            ),
+            &["event_type"],
+        )?;
+        let errors_total = IntCounterVec::new(
+            Opts::new(
+                "gateway_normalize_errors_total",
+                "Total processing errors, by event type and error variant",
+            ),
+            &["event_type", "error"],
+        )?;
+        let processing_duration_seconds = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "gateway_normalize_processing_duration_seconds",
+                "Time to normalize a single gateway event, by event type",
+            ),
+            &["event_type"],
+        )?;
+        let in_flight_apply_calls = IntGauge::new(
+            "gateway_normalize_in_flight_apply_calls",
+            "Number of SplitProcessor::apply calls currently in flight, \
+             including time spent blocked on the audit log RwLock",
+        )?;
+
+        registry.register(Box::new(processed_total.clone()))?;
+        registry.register(Box::new(errors_total.clone()))?;
+        registry.register(Box::new(processing_duration_seconds.clone()))?;
+        registry.register(Box::new(in_flight_apply_calls.clone()))?;
+
+        Ok(Self {
+            registry,
+            processed_total,
+            errors_total,
+            processing_duration_seconds,
+            in_flight_apply_calls,
+        })
+    }
+
+    /// Records a single processing attempt's outcome and latency
+    pub fn record_processed(
+        &self,
+        event_type: &str,
+        elapsed: std::time::Duration,
+        result: &Result<crate::event::NormalizedEvent, ProcessingError>,
+    ) {
+        self.processed_total.with_label_values(&[event_type]).inc();
+        self.processing_duration_seconds
+            .with_label_values(&[event_type])
+            .observe(elapsed.as_secs_f64());
+
+        if let Err(error) = result {
+            self.errors_total
+                .with_label_values(&[event_type, error_variant_label(error)])
+                .inc();
+        }
+    }
+
+    /// A guard that increments the in-flight gauge on creation and
+    /// decrements it on drop, regardless of how the `apply` call finishes
+    pub fn track_apply(&self) -> InFlightGuard<'_> {
+        self.in_flight_apply_calls.inc();
+        InFlightGuard { metrics: self }
+    }
+
+    /// Renders all registered collectors in Prometheus text exposition format
+    pub fn render(&self) -> anyhow::Result<String> {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&metric_families, &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+/// RAII guard returned by `Metrics::track_apply`
+pub struct InFlightGuard<'a> {
+    metrics: &'a Metrics,
+}
+
+impl Drop for InFlightGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.in_flight_apply_calls.dec();
+    }
+}
@@ -0,0 +1,222 @@
+//! Role and permission resolution, backing `Context::has_perms`.
+//!
+//! Folds a guild member's roles plus the `@everyone` base into an effective
+//! permission set, applies the administrator override, and then applies
+//! channel-level permission overwrites, the same precedence Discord itself
+//! uses. Per-guild role/permission state is cached so that a permission
+//! check doesn't hit the API on every event.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::{OnceCell, RwLock};
+use twilight_http::Client;
+use twilight_model::guild::{Permissions, Role};
+use twilight_model::id::{GuildId, RoleId, UserId};
+
+/// How long a guild's resolved role state (and the bot's membership in it)
+/// is trusted before being re-fetched
+const CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The pieces of guild state needed to resolve permissions for any member,
+/// cached together so a single invalidation covers both
+#[derive(Debug)]
+struct CachedGuildState {
+    roles: HashMap<RoleId, Permissions>,
+    /// The bot's own role ids in this guild, cached alongside the role list
+    /// since both are invalidated by the same kind of gateway event
+    bot_member_role_ids: Vec<RoleId>,
+    fetched_at: Instant,
+}
+
+/// Resolves whether the bot's member has a given set of permissions in a
+/// guild (and optionally a specific channel), caching per-guild role state
+/// so a check doesn't hit the Discord API on every event
+#[derive(Debug)]
+pub struct PermissionResolver {
+    client: Client,
+    /// The bot's own user id never changes for the process's lifetime,
+    /// so it's fetched at most once regardless of how many guilds are resolved
+    bot_user_id: OnceCell<UserId>,
+    cache: RwLock<HashMap<GuildId, CachedGuildState>>,
+}
+
+impl PermissionResolver {
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        Self {
+            client,
+            bot_user_id: OnceCell::new(),
+            cache: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Invalidates the cached role state for a guild, forcing the next
+    /// `resolve` call to re-fetch it. Should be called when gateway events
+    /// indicate a role or the bot's own roles changed.
+    pub async fn invalidate(&self, guild_id: GuildId) {
+        self.cache.write().await.remove(&guild_id);
+    }
+
+    /// Determines whether the bot has `required` permissions in `guild_id`,
+    /// optionally narrowed to a specific channel's effective overwrites.
+    pub async fn resolve(
+        &self,
+        guild_id: GuildId,
+        channel_id: Option<twilight_model::id::ChannelId>,
+        required: Permissions,
+    ) -> Result<bool, anyhow::Error> {
+        let bot_user_id = self.current_user_id().await?;
+        let (role_permissions, member_role_ids) = self.guild_state(guild_id, bot_user_id).await?;
+
+        // `@everyone`'s role id is always the guild id
+        let everyone_permissions = role_permissions
+            .get(&RoleId(guild_id.0))
+            .copied()
+            .unwrap_or_else(Permissions::empty);
+
+        let mut effective = everyone_permissions;
+        for role_id in &member_role_ids {
+            if let Some(permissions) = role_permissions.get(role_id) {
+                effective |= *permissions;
+            }
+        }
+
+        if effective.contains(Permissions::ADMINISTRATOR) {
+            return Ok(true);
+        }
+
+        if let Some(channel_id) = channel_id {
+            effective = self
+                .apply_channel_overwrites(channel_id, bot_user_id, &member_role_ids, effective)
+                .await?;
+
+            if effective.contains(Permissions::ADMINISTRATOR) {
+                return Ok(true);
+            }
+        }
+
+        Ok(effective.contains(required))
+    }
+
+    /// Returns the bot's own user id, fetched once and reused for the
+    /// lifetime of the process since it never changes
+    async fn current_user_id(&self) -> Result<UserId, anyhow::Error> {
+        self.bot_user_id
+            .get_or_try_init(|| async {
+                Ok::<_, anyhow::Error>(self.client.current_user().exec().await?.model().await?.id)
+            })
+            .await
+            .copied()
+    }
+
+    async fn fetch_member_role_ids(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<RoleId>, anyhow::Error> {
+        let member = self
+            .client
+            .guild_member(guild_id, user_id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+        Ok(member.roles)
+    }
+
+    /// Returns the guild's roles mapped to their permission bits, along with
+    /// the bot member's own role ids in that guild, re-fetching both only
+    /// when the cached entry is missing or stale
+    async fn guild_state(
+        &self,
+        guild_id: GuildId,
+        bot_user_id: UserId,
+    ) -> Result<(HashMap<RoleId, Permissions>, Vec<RoleId>), anyhow::Error> {
+        {
+            let cache = self.cache.read().await;
+            if let Some(cached) = cache.get(&guild_id) {
+                if cached.fetched_at.elapsed() < CACHE_TTL {
+                    return Ok((cached.roles.clone(), cached.bot_member_role_ids.clone()));
+                }
+            }
+        }
+
+        let roles: Vec<Role> = self
+            .client
+            .roles(guild_id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+        let role_permissions: HashMap<RoleId, Permissions> =
+            roles.into_iter().map(|role| (role.id, role.permissions)).collect();
+        let bot_member_role_ids = self.fetch_member_role_ids(guild_id, bot_user_id).await?;
+
+        self.cache.write().await.insert(
+            guild_id,
+            CachedGuildState {
+                roles: role_permissions.clone(),
+                bot_member_role_ids: bot_member_role_ids.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+
+        Ok((role_permissions, bot_member_role_ids))
+    }
+
+    /// Applies a channel's permission overwrites on top of the base guild
+    /// permissions, in Discord's own precedence: `@everyone` overwrite,
+    /// then role overwrites, then the member-specific overwrite
+    async fn apply_channel_overwrites(
+        &self,
+        channel_id: twilight_model::id::ChannelId,
+        user_id: UserId,
+        member_role_ids: &[RoleId],
+        base: Permissions,
+    ) -> Result<Permissions, anyhow::Error> {
+        let channel = self
+            .client
+            .channel(channel_id)
+            .exec()
+            .await?
+            .model()
+            .await?;
+
+        let Some(overwrites) = channel.permission_overwrites() else {
+            return Ok(base);
+        };
+
+        let mut permissions = base;
+        let guild_id = channel
+            .guild_id()
+            .ok_or_else(|| anyhow::anyhow!("channel {} is not a guild channel", channel_id))?;
+
+        for overwrite in overwrites {
+            if overwrite.id.0 == guild_id.0 {
+                permissions = (permissions & !overwrite.deny) | overwrite.allow;
+            }
+        }
+        // Union every matching role overwrite's allow/deny bits before
+        // applying them as a single step, rather than folding them in one at
+        // a time: applying them individually makes the result depend on
+        // `overwrites`' iteration order (an allow from one role can be
+        // clobbered by a deny from another purely because it came later),
+        // which doesn't match Discord's own role-overwrite resolution
+        let mut role_allow = Permissions::empty();
+        let mut role_deny = Permissions::empty();
+        for overwrite in overwrites {
+            if member_role_ids.iter().any(|role_id| role_id.0 == overwrite.id.0) {
+                role_allow |= overwrite.allow;
+                role_deny |= overwrite.deny;
+            }
+        }
+        permissions = (permissions & !role_deny) | role_allow;
+        for overwrite in overwrites {
+            if overwrite.id.0 == user_id.0 {
+                permissions = (permissions & !overwrite.deny) | overwrite.allow;
+            }
+        }
+
+        Ok(permissions)
+    }
+}
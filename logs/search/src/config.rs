@@ -0,0 +1,93 @@
+//! Runtime configuration for the search service, loaded once at startup
+//! from the YAML file given on the command line and threaded through the
+//! rest of the service as an `Arc<Configuration>`.
+
+use serde::{Deserialize, Deserializer};
+use slog::{Drain, Level, Logger};
+use sloggers::terminal::{Destination, TerminalLoggerBuilder};
+use sloggers::Build;
+
+/// Top-level configuration for the service
+#[derive(Deserialize, Debug, Clone)]
+pub struct Configuration {
+    /// Address and TLS settings Rocket binds its HTTP listener to
+    pub rocket: rocket::Config,
+    /// Governs how `main`'s default log drain is built
+    pub logging: LoggingConfig,
+    /// Opts the service into the `/logs/stream` SSE route when present;
+    /// left unset, no log stream is mounted
+    #[serde(default)]
+    pub log_streaming: Option<LogStreamingConfig>,
+}
+
+impl Configuration {
+    /// Loads and parses the configuration file at `path`
+    pub fn try_load(path: &str) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_yaml::from_str(&contents)?)
+    }
+}
+
+/// Configures the terminal logger `main` builds by default
+#[derive(Deserialize, Debug, Clone)]
+pub struct LoggingConfig {
+    #[serde(default = "default_level", deserialize_with = "deserialize_level")]
+    pub level: Level,
+}
+
+impl sloggers::Config for LoggingConfig {
+    type Builder = TerminalLoggerBuilder;
+
+    fn try_to_builder(&self) -> sloggers::Result<Self::Builder> {
+        let mut builder = TerminalLoggerBuilder::new();
+        builder.level(self.level);
+        builder.destination(Destination::Stdout);
+        Ok(builder)
+    }
+}
+
+impl LoggingConfig {
+    /// Builds a logger the same way `build_logger` (the `sloggers::Config`
+    /// default method) does, but splices `wrap` around the terminal drain
+    /// before it's fused and handed to `slog_async`, so callers can fan
+    /// records out to something in addition to stdout (e.g.
+    /// `streaming::BroadcastDrain`) without losing the async wrapper every
+    /// other logger in this service gets.
+    pub fn build_logger_with_drain<F, D>(&self, wrap: F) -> sloggers::Result<Logger>
+    where
+        F: FnOnce(slog::Fuse<slog_term::FullFormat<slog_term::TermDecorator>>) -> D,
+        D: Drain<Ok = (), Err = slog::Never> + Send + Sync + 'static,
+    {
+        let decorator = slog_term::TermDecorator::new().stdout().build();
+        let drain = slog_term::FullFormat::new(decorator).build().fuse();
+        let drain = wrap(drain).filter_level(self.level).fuse();
+        let drain = slog_async::Async::new(drain).build().fuse();
+        Ok(Logger::root(drain, slog::o!()))
+    }
+}
+
+fn default_level() -> Level {
+    Level::Info
+}
+
+/// Configures the optional `/logs/stream` SSE route
+#[derive(Deserialize, Debug, Clone)]
+pub struct LogStreamingConfig {
+    /// Records below this level are never forwarded to subscribers
+    #[serde(deserialize_with = "deserialize_level")]
+    pub min_level: Level,
+    /// How many records a lagging subscriber can fall behind by before it
+    /// starts missing them
+    pub channel_capacity: usize,
+}
+
+/// `slog::Level` has no `Deserialize` impl of its own, so config fields
+/// using it go through its `FromStr` impl instead (`"info"`, `"debug"`, etc.)
+fn deserialize_level<'de, D>(deserializer: D) -> Result<Level, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    raw.parse()
+        .map_err(|()| serde::de::Error::custom(format!("invalid log level: {raw}")))
+}
@@ -6,9 +6,11 @@ mod elasticsearch;
 mod fairings;
 mod graphql;
 mod rpc;
+mod streaming;
 
 use crate::config::Configuration;
 use crate::graphql::SearchProvider;
+use crate::streaming::{BroadcastDrain, LogStream};
 use anyhow::Context;
 use rocket::response::content::Html;
 use rocket::response::status::BadRequest;
@@ -29,11 +31,21 @@ async fn main() -> anyhow::Result<()> {
     );
     let config = Arc::new(Configuration::try_load(&config_path)?);
 
+    // Set up the log stream before the logger, so that the broadcast drain
+    // can be spliced into the main drain chain at construction time
+    let log_stream = config
+        .log_streaming
+        .as_ref()
+        .map(|streaming_config| LogStream::new(streaming_config.min_level, streaming_config.channel_capacity));
+
     // Set up the logger from the config
-    let logger = config
-        .logging
-        .build_logger()
-        .context("could not build logger from config values")?;
+    let logger = match &log_stream {
+        Some(stream) => config.logging.build_logger_with_drain(|inner| {
+            BroadcastDrain::new(inner, Arc::clone(stream))
+        }),
+        None => config.logging.build_logger(),
+    }
+    .context("could not build logger from config values")?;
 
     slog::info!(
         logger,
@@ -44,7 +56,7 @@ async fn main() -> anyhow::Result<()> {
     slog::debug!(logger, "configuration dump"; "config" => ?config);
     slog::debug!(logger, "env dump"; "env" => ?std::env::vars().collect::<Vec<_>>());
 
-    match run(config, logger.clone()).await {
+    match run(config, logger.clone(), log_stream).await {
         Ok(_) => slog::info!(logger, "service exited";),
         Err(err) => {
             slog::error!(
@@ -58,21 +70,32 @@ async fn main() -> anyhow::Result<()> {
 }
 
 /// Attempts to initialize the service and listen GraphQL requests
-async fn run(config: Arc<Configuration>, logger: Logger) -> anyhow::Result<()> {
+async fn run(
+    config: Arc<Configuration>,
+    logger: Logger,
+    log_stream: Option<Arc<LogStream>>,
+) -> anyhow::Result<()> {
     // Connect to Elasticsearch
     let elasticsearch =
         Arc::new(connect::connect_to_elasticsearch(Arc::clone(&config), logger.clone()).await?);
 
     let search = SearchProvider::new(Arc::clone(&elasticsearch), Arc::clone(&config));
-    rocket::custom(config.rocket.clone())
+    let mut rocket = rocket::custom(config.rocket.clone())
         .manage(search)
         .mount("/", rocket::routes![playground, post_graphql, get_graphql])
         .attach(fairings::request_id::Fairing::new())
         .attach(fairings::attach_logger::Fairing::new(logger.clone()))
-        .attach(fairings::request_logging::Fairing::new(logger.clone()))
-        .launch()
-        .await
-        .expect("server to launch");
+        .attach(fairings::request_logging::Fairing::new(logger.clone()));
+
+    // Opt-in live log tailing: mounted only when configured, so that
+    // deployments that don't want a `/logs/stream` route don't get one
+    if let Some(stream) = log_stream {
+        rocket = rocket
+            .manage(stream)
+            .mount("/", rocket::routes![streaming::logs_stream]);
+    }
+
+    rocket.launch().await.expect("server to launch");
 
     Ok(())
 }
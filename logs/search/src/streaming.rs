@@ -0,0 +1,167 @@
+//! Live log tailing: a slog drain that forwards records into a
+//! `tokio::sync::broadcast` channel, plus the Rocket route that exposes
+//! that channel as an SSE feed.
+//!
+//! The key design point is zero added cost when nobody is subscribed:
+//! `receiver_count()` is checked before any record is cloned or serialized,
+//! so enabling this drain is free until an operator actually connects.
+
+use rocket::http::Status;
+use rocket::request::{self, FromRequest, Outcome, Request};
+use rocket::response::stream::{Event, EventStream};
+use rocket::State;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+/// Environment variable holding the shared secret required to open the
+/// `/logs/stream` feed. Every log record (including anything a processor
+/// happened to log at debug level) is otherwise visible to any client that
+/// can reach this service, so the route refuses every request until this is set.
+const AUTH_TOKEN_ENV: &str = "LOGS_STREAM_AUTH_TOKEN";
+
+/// Request guard gating `/logs/stream` behind an `Authorization: Bearer
+/// <token>` header matching `LOGS_STREAM_AUTH_TOKEN`
+pub struct StreamAuth;
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for StreamAuth {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let Ok(expected) = std::env::var(AUTH_TOKEN_ENV) else {
+            // No token configured: fail closed rather than streaming logs unauthenticated
+            return Outcome::Error((Status::ServiceUnavailable, ()));
+        };
+
+        let provided = request
+            .headers()
+            .get_one("Authorization")
+            .and_then(|header| header.strip_prefix("Bearer "));
+
+        match provided {
+            Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => {
+                Outcome::Success(Self)
+            }
+            _ => Outcome::Error((Status::Unauthorized, ())),
+        }
+    }
+}
+
+/// Compares two byte strings in constant time, so response timing can't be
+/// used as a side channel to guess the expected token byte-by-byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// A single log record forwarded to stream subscribers, serialized eagerly
+/// (only once a subscriber exists) so that subscribers just re-broadcast it
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct StreamedRecord {
+    pub level: String,
+    pub message: String,
+    #[serde(rename = "kv")]
+    pub key_values: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Shared state handed to both the wrapping drain and the Rocket route
+pub struct LogStream {
+    sender: broadcast::Sender<StreamedRecord>,
+    min_level: Level,
+}
+
+impl LogStream {
+    /// Creates a new log stream, dropping records below `min_level`
+    /// and buffering up to `capacity` records per lagging subscriber
+    #[must_use]
+    pub fn new(min_level: Level, capacity: usize) -> Arc<Self> {
+        let (sender, _) = broadcast::channel(capacity);
+        Arc::new(Self { sender, min_level })
+    }
+
+    /// A cheap check for whether forwarding a record is worth the cost
+    /// of serializing it
+    fn has_subscribers(&self) -> bool {
+        self.sender.receiver_count() > 0
+    }
+}
+
+/// A `slog::Drain` that wraps another drain, forwarding every record that
+/// passes `min_level` into the broadcast channel, but only when at least one
+/// subscriber is listening.
+pub struct BroadcastDrain<D> {
+    inner: D,
+    stream: Arc<LogStream>,
+}
+
+impl<D> BroadcastDrain<D> {
+    pub const fn new(inner: D, stream: Arc<LogStream>) -> Self {
+        Self { inner, stream }
+    }
+}
+
+impl<D> Drain for BroadcastDrain<D>
+where
+    D: Drain<Ok = (), Err = slog::Never>,
+{
+    type Ok = ();
+    type Err = slog::Never;
+
+    fn log(&self, record: &Record<'_>, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if self.stream.has_subscribers() && record.level().is_at_least(self.stream.min_level) {
+            let streamed = StreamedRecord {
+                level: record.level().as_str().to_string(),
+                message: record.msg().to_string(),
+                key_values: serialize_kv(record, values),
+            };
+            // A send error just means every subscriber disconnected between
+            // the `has_subscribers` check and now; nothing to do about it
+            let _ = self.stream.sender.send(streamed);
+        }
+
+        self.inner.log(record, values)
+    }
+}
+
+/// Flattens a record's structured key-values into a JSON object
+fn serialize_kv(
+    record: &Record<'_>,
+    values: &OwnedKVList,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut serializer = MapSerializer(serde_json::Map::new());
+    let _ = values.serialize(record, &mut serializer);
+    let _ = record.kv().serialize(record, &mut serializer);
+    serializer.0
+}
+
+/// A `slog::Serializer` that collects every key-value pair
+/// into a flat JSON object, formatting values with their `Display` impl
+struct MapSerializer(serde_json::Map<String, serde_json::Value>);
+
+impl slog::Serializer for MapSerializer {
+    fn emit_arguments(&mut self, key: slog::Key, val: &std::fmt::Arguments<'_>) -> slog::Result {
+        self.0.insert(key.to_string(), serde_json::Value::from(val.to_string()));
+        Ok(())
+    }
+}
+
+/// `GET /logs/stream`: a live feed of log records as Server-Sent Events,
+/// gated behind `StreamAuth` so it isn't an unauthenticated firehose of
+/// application logs
+#[rocket::get("/logs/stream")]
+pub fn logs_stream(stream: &State<Arc<LogStream>>, _auth: StreamAuth) -> EventStream![] {
+    let mut receiver = stream.sender.subscribe();
+    EventStream! {
+        loop {
+            match receiver.recv().await {
+                Ok(record) => yield Event::json(&record),
+                // A lagging subscriber missed some records; keep tailing from here
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Alias management, supporting zero-downtime (blue/green) reindexing:
+//! build a new timestamped index, bulk-load it, atomically swap a stable
+//! read alias over to it, then delete the stale physical indices.
+
+use crate::elasticsearch::{Client, StatusCode};
+use elasticsearch::indices::{
+    IndicesDeleteParts, IndicesGetAliasParts, IndicesUpdateAliasesParts,
+};
+use elasticsearch::Error as LibError;
+use std::collections::HashSet;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SwapAliasError {
+    #[error("swapping the alias failed")]
+    Failed(#[source] LibError),
+    #[error("swapping the alias failed with a non-success status code {0}")]
+    ErrorStatusCode(StatusCode),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum IndicesForAliasError {
+    #[error("looking up indices for the alias failed")]
+    Failed(#[source] LibError),
+    #[error("looking up indices for the alias failed with a non-success status code {0}")]
+    ErrorStatusCode(StatusCode),
+    #[error("failed to decode response body from elasticsearch")]
+    FailedToDecode(#[source] LibError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeleteIndexError {
+    #[error("deleting the index failed")]
+    Failed(#[source] LibError),
+    #[error("deleting the index failed with a non-success status code {0}")]
+    ErrorStatusCode(StatusCode),
+}
+
+impl Client {
+    /// Atomically flips `alias` from pointing at `old_indices` to pointing at
+    /// `new_index`, issuing a single `_aliases` request so that there is no
+    /// window in which readers see neither (or both) index generations.
+    pub async fn swap_alias(
+        &self,
+        alias: impl AsRef<str>,
+        new_index: impl AsRef<str>,
+        old_indices: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<(), SwapAliasError> {
+        let alias_ref = alias.as_ref();
+        let new_index_ref = new_index.as_ref();
+
+        let mut actions = vec![serde_json::json!({
+            "add": { "index": new_index_ref, "alias": alias_ref }
+        })];
+        actions.extend(old_indices.into_iter().map(|index| {
+            serde_json::json!({
+                "remove": { "index": index.as_ref(), "alias": alias_ref }
+            })
+        }));
+
+        let response = self
+            .inner
+            .indices()
+            .update_aliases(IndicesUpdateAliasesParts::None)
+            .body(serde_json::json!({ "actions": actions }))
+            .send()
+            .await
+            .map_err(SwapAliasError::Failed)?;
+
+        let status_code = response.status_code();
+        if status_code.is_success() {
+            Ok(())
+        } else {
+            Err(SwapAliasError::ErrorStatusCode(status_code))
+        }
+    }
+
+    /// Returns the set of concrete indices that `alias` currently points at
+    pub async fn indices_for_alias(
+        &self,
+        alias: impl AsRef<str>,
+    ) -> Result<HashSet<String>, IndicesForAliasError> {
+        let alias_ref = alias.as_ref();
+
+        let response = self
+            .inner
+            .indices()
+            .get_alias(IndicesGetAliasParts::Name(&[alias_ref]))
+            .send()
+            .await
+            .map_err(IndicesForAliasError::Failed)?;
+
+        let status_code = response.status_code();
+        if status_code == StatusCode::NOT_FOUND {
+            // No indices currently carry this alias
+            return Ok(HashSet::new());
+        }
+        if !status_code.is_success() {
+            return Err(IndicesForAliasError::ErrorStatusCode(status_code));
+        }
+
+        let body = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(IndicesForAliasError::FailedToDecode)?;
+
+        let indices = body
+            .as_object()
+            .map(|map| map.keys().cloned().collect())
+            .unwrap_or_default();
+
+        Ok(indices)
+    }
+
+    /// Deletes the given index outright. Intended to be called against stale
+    /// physical indices only after `swap_alias` has moved readers off of them.
+    pub async fn delete_index(&self, index: impl AsRef<str>) -> Result<(), DeleteIndexError> {
+        let response = self
+            .inner
+            .indices()
+            .delete(IndicesDeleteParts::Index(&[index.as_ref()]))
+            .send()
+            .await
+            .map_err(DeleteIndexError::Failed)?;
+
+        let status_code = response.status_code();
+        if status_code.is_success() {
+            Ok(())
+        } else {
+            Err(DeleteIndexError::ErrorStatusCode(status_code))
+        }
+    }
+}
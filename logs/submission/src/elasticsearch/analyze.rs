@@ -0,0 +1,74 @@
+//! The `_analyze` API, useful for verifying at startup that a field's
+//! configured analyzer (edge-ngram, language analyzers, etc.) tokenizes
+//! sample input the way the mappings expect, before any documents are
+//! indexed against it.
+
+use crate::elasticsearch::{Client, StatusCode};
+use elasticsearch::Error as LibError;
+use serde::Deserialize;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AnalyzeError {
+    #[error("submitting the analyze request failed")]
+    Failed(#[source] LibError),
+    #[error("analyze request failed with a non-success status code {0}")]
+    ErrorStatusCode(StatusCode),
+    #[error("failed to decode response body from elasticsearch")]
+    FailedToDecode(#[source] LibError),
+}
+
+/// A single token produced by running an analyzer over sample text
+#[derive(Clone, Debug, Deserialize)]
+pub struct Token {
+    pub token: String,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    pub position: u64,
+    #[serde(rename = "type")]
+    pub token_type: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnalyzeResponse {
+    tokens: Vec<Token>,
+}
+
+impl Client {
+    /// Runs the named `analyzer` (as configured on `index`) over `text`,
+    /// returning the tokens it produced. Intended to be called right after
+    /// `ensure_index_exists` to assert that the analyzer is configured as
+    /// expected, catching mapping misconfiguration before documents are
+    /// indexed.
+    pub async fn analyze(
+        &self,
+        index: impl AsRef<str>,
+        analyzer: impl AsRef<str>,
+        text: impl Into<String>,
+    ) -> Result<Vec<Token>, AnalyzeError> {
+        let response = self
+            .inner
+            .indices()
+            .analyze(elasticsearch::indices::IndicesAnalyzeParts::Index(
+                index.as_ref(),
+            ))
+            .body(serde_json::json!({
+                "analyzer": analyzer.as_ref(),
+                "text": text.into(),
+            }))
+            .send()
+            .await
+            .map_err(AnalyzeError::Failed)?;
+
+        let status_code = response.status_code();
+        if !status_code.is_success() {
+            return Err(AnalyzeError::ErrorStatusCode(status_code));
+        }
+
+        let decoded: AnalyzeResponse = response
+            .json()
+            .await
+            .map_err(AnalyzeError::FailedToDecode)?;
+
+        Ok(decoded.tokens)
+    }
+}
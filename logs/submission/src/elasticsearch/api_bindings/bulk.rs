@@ -0,0 +1,44 @@
+//! Hand-made bindings for the `_bulk` API response body
+
+use serde::Deserialize;
+
+/// The top-level `_bulk` response body
+#[derive(Clone, Debug, Deserialize)]
+pub struct Response {
+    pub took: i64,
+    pub errors: bool,
+    pub items: Vec<ResultItem>,
+}
+
+/// A single bulk response item.
+/// Exactly one of these fields is populated,
+/// matching whichever action the corresponding request item specified.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResultItem {
+    pub create: Option<ResultItemAction>,
+    pub delete: Option<ResultItemAction>,
+    pub index: Option<ResultItemAction>,
+    pub update: Option<ResultItemAction>,
+}
+
+/// The result of a single action within a bulk response item
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResultItemAction {
+    #[serde(rename = "_index")]
+    pub index: String,
+    #[serde(rename = "_id")]
+    pub id: String,
+    /// The per-item HTTP-equivalent status code.
+    /// A bulk request can return an overall 200
+    /// while individual items fail with e.g. 429 or 400.
+    pub status: u16,
+    pub error: Option<ResultItemError>,
+}
+
+/// The error body of a failed bulk item, when present
+#[derive(Clone, Debug, Deserialize)]
+pub struct ResultItemError {
+    #[serde(rename = "type")]
+    pub error_type: String,
+    pub reason: String,
+}
@@ -0,0 +1,4 @@
+//! Hand-made bindings for Elasticsearch response bodies
+//! that aren't otherwise typed by the `elasticsearch` crate
+
+pub mod bulk;
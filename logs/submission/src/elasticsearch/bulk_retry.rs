@@ -0,0 +1,111 @@
+//! Automatic retry of throttled and partially-failed bulk items.
+//!
+//! A bulk request can return an overall HTTP 200 while individual items fail
+//! with a retryable status, such as `429 too_many_requests` when a shard is
+//! under write pressure. This module resubmits just the failed subset with
+//! exponential backoff and jitter, leaving non-retryable failures
+//! (e.g. `400` mapping errors) in the returned `BulkStatus`.
+
+use crate::elasticsearch::{BulkError, BulkOperation, BulkStatus, Client};
+use rand::Rng;
+use std::time::Duration;
+
+/// HTTP-equivalent per-item statuses that are considered transient
+/// and therefore safe to retry
+const RETRYABLE_STATUSES: [u16; 2] = [429, 503];
+
+/// Governs how `Client::bulk_with_retry` resubmits failed items
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts (including the first) before giving up
+    /// and returning the remaining retryable failures as-is
+    pub max_attempts: u32,
+    /// The backoff duration before the second attempt; doubles each attempt after
+    pub initial_backoff: Duration,
+    /// The maximum backoff duration, capping the exponential growth
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Client {
+    /// Submits a bulk operation request, retrying the subset of items that
+    /// fail with a retryable per-item status (`429`/`503`) up to
+    /// `policy.max_attempts` times, with exponential backoff and jitter
+    /// between attempts. Non-retryable failures are left in the result.
+    pub async fn bulk_with_retry(
+        &self,
+        index: impl AsRef<str>,
+        operations: &[BulkOperation],
+        policy: &RetryPolicy,
+    ) -> Result<BulkStatus, BulkError> {
+        let index_ref = index.as_ref();
+        let mut pending: Vec<&BulkOperation> = operations.iter().collect();
+        let mut settled_items = Vec::new();
+        let mut took_total = 0_i64;
+        let mut attempt = 0_u32;
+
+        loop {
+            attempt += 1;
+            let status = self.bulk(index_ref, pending.iter().copied()).await?;
+            took_total += status.took;
+
+            let (retryable, settled): (Vec<_>, Vec<_>) = status
+                .items
+                .into_iter()
+                .partition(|item| RETRYABLE_STATUSES.contains(&item.action().status));
+            settled_items.extend(settled);
+
+            if retryable.is_empty() {
+                break;
+            }
+
+            if attempt >= policy.max_attempts {
+                slog::warn!(
+                    self.logger,
+                    "giving up on retrying bulk items after exhausting max attempts";
+                    "attempts" => attempt,
+                    "remaining_failures" => retryable.len(),
+                );
+                settled_items.extend(retryable);
+                break;
+            }
+
+            let retry_ids: std::collections::HashSet<String> =
+                retryable.iter().map(|item| item.id().clone()).collect();
+            pending.retain(|op| retry_ids.contains(op.id()));
+
+            tokio::time::sleep(backoff_with_jitter(policy, attempt)).await;
+        }
+
+        Ok(BulkStatus {
+            took: took_total,
+            errors: settled_items
+                .iter()
+                .any(|item| item.action().error.is_some()),
+            items: settled_items,
+        })
+    }
+}
+
+/// Computes the delay before the next attempt: exponential backoff from
+/// `policy.initial_backoff`, capped at `policy.max_backoff`, with full jitter
+/// (a random delay in `[0, computed_delay]`).
+fn backoff_with_jitter(policy: &RetryPolicy, attempt: u32) -> Duration {
+    let exponent = attempt.saturating_sub(1).min(16);
+    let capped = policy
+        .initial_backoff
+        .saturating_mul(1_u32.checked_shl(exponent).unwrap_or(u32::MAX))
+        .min(policy.max_backoff);
+
+    let jittered_millis = rand::thread_rng().gen_range(0..=capped.as_millis().max(1) as u64);
+    Duration::from_millis(jittered_millis)
+}
@@ -0,0 +1,202 @@
+//! A buffered, background bulk sink built on top of `Client::bulk`,
+//! inspired by the rlink Elasticsearch connector's buffered-handover design.
+//!
+//! Callers push `BulkOperation`s onto an async channel; a background task
+//! accumulates them and flushes whenever the buffer hits a size limit or a
+//! linger timer fires, whichever comes first, so call sites don't need to
+//! hand-batch operations themselves.
+
+use crate::elasticsearch::{BulkError, BulkOperation, BulkStatus, Client};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Limits that govern when the background task flushes its buffer
+#[derive(Clone, Debug)]
+pub struct FlushPolicy {
+    /// Flush once this many operations have been buffered
+    pub max_operations: usize,
+    /// Flush once the serialized size of the buffered operations
+    /// (sum of action + source bytes) reaches this many bytes
+    pub max_bytes: usize,
+    /// Flush once this much time has elapsed since the first operation
+    /// was buffered since the last flush, regardless of size
+    pub max_linger: Duration,
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        Self {
+            max_operations: 500,
+            max_bytes: 5 * 1024 * 1024,
+            max_linger: Duration::from_secs(5),
+        }
+    }
+}
+
+/// The result of a single background flush, surfaced to the caller
+/// so it can observe `errors`/`took` (or the error that occurred)
+#[derive(Debug)]
+pub enum FlushOutcome {
+    Flushed(BulkStatus),
+    Failed(BulkError),
+}
+
+enum Message {
+    Push(BulkOperation, usize),
+    Flush(Option<oneshot::Sender<()>>),
+    Close(oneshot::Sender<()>),
+}
+
+/// A handle to a running background bulk-indexing task.
+/// Dropping this handle closes the channel, after which the background task
+/// flushes what it has buffered and stops on its own, but nothing waits for
+/// that; call `close()` to drain and stop it and wait until it has finished.
+pub struct BulkIndexer {
+    sender: mpsc::UnboundedSender<Message>,
+    join_handle: JoinHandle<()>,
+}
+
+impl BulkIndexer {
+    /// Spawns a background task that buffers operations for `index` and
+    /// flushes them to `client` according to `policy`. Returns the indexer
+    /// handle alongside a receiver of per-flush outcomes.
+    #[must_use]
+    pub fn spawn(
+        client: Arc<Client>,
+        index: impl Into<String>,
+        policy: FlushPolicy,
+    ) -> (Self, mpsc::UnboundedReceiver<FlushOutcome>) {
+        let (message_tx, message_rx) = mpsc::unbounded_channel();
+        let (outcome_tx, outcome_rx) = mpsc::unbounded_channel();
+        let index = index.into();
+
+        let join_handle = tokio::spawn(run_background_task(
+            client,
+            index,
+            policy,
+            message_rx,
+            outcome_tx,
+        ));
+
+        (
+            Self {
+                sender: message_tx,
+                join_handle,
+            },
+            outcome_rx,
+        )
+    }
+
+    /// Pushes an operation onto the buffer, to be sent on the next flush
+    pub fn push(&self, operation: BulkOperation) {
+        let size = operation.serialized_len();
+        // The background task is only gone after `close()` resolves,
+        // at which point no more pushes should be attempted
+        let _ = self.sender.send(Message::Push(operation, size));
+    }
+
+    /// Forces an immediate flush of any buffered operations,
+    /// without waiting for the size or linger limits to be hit
+    pub async fn flush(&self) {
+        let (tx, rx) = oneshot::channel();
+        if self.sender.send(Message::Flush(Some(tx))).is_ok() {
+            let _ = rx.await;
+        }
+    }
+
+    /// Flushes any remaining buffered operations and stops the background task
+    pub async fn close(self) {
+        let (tx, rx) = oneshot::channel();
+        if self.sender.send(Message::Close(tx)).is_ok() {
+            let _ = rx.await;
+        }
+        let _ = self.join_handle.await;
+    }
+}
+
+async fn run_background_task(
+    client: Arc<Client>,
+    index: String,
+    policy: FlushPolicy,
+    mut message_rx: mpsc::UnboundedReceiver<Message>,
+    outcome_tx: mpsc::UnboundedSender<FlushOutcome>,
+) {
+    let mut buffer: Vec<BulkOperation> = Vec::new();
+    let mut buffered_bytes: usize = 0;
+    let mut linger_deadline: Option<Instant> = None;
+
+    loop {
+        let sleep_until_linger = async {
+            match linger_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            message = message_rx.recv() => {
+                match message {
+                    Some(Message::Push(operation, size)) => {
+                        if linger_deadline.is_none() {
+                            linger_deadline = Some(Instant::now() + policy.max_linger);
+                        }
+                        buffered_bytes += size;
+                        buffer.push(operation);
+
+                        if buffer.len() >= policy.max_operations || buffered_bytes >= policy.max_bytes {
+                            flush_buffer(&client, &index, &mut buffer, &mut buffered_bytes, &outcome_tx).await;
+                            linger_deadline = None;
+                        }
+                    }
+                    Some(Message::Flush(ack)) => {
+                        flush_buffer(&client, &index, &mut buffer, &mut buffered_bytes, &outcome_tx).await;
+                        linger_deadline = None;
+                        if let Some(ack) = ack {
+                            let _ = ack.send(());
+                        }
+                    }
+                    Some(Message::Close(ack)) => {
+                        flush_buffer(&client, &index, &mut buffer, &mut buffered_bytes, &outcome_tx).await;
+                        let _ = ack.send(());
+                        return;
+                    }
+                    None => {
+                        // All senders dropped; drain what's left and stop
+                        flush_buffer(&client, &index, &mut buffer, &mut buffered_bytes, &outcome_tx).await;
+                        return;
+                    }
+                }
+            }
+            () = sleep_until_linger => {
+                flush_buffer(&client, &index, &mut buffer, &mut buffered_bytes, &outcome_tx).await;
+                linger_deadline = None;
+            }
+        }
+    }
+}
+
+async fn flush_buffer(
+    client: &Client,
+    index: &str,
+    buffer: &mut Vec<BulkOperation>,
+    buffered_bytes: &mut usize,
+    outcome_tx: &mpsc::UnboundedSender<FlushOutcome>,
+) {
+    if buffer.is_empty() {
+        return;
+    }
+
+    let operations = std::mem::take(buffer);
+    *buffered_bytes = 0;
+
+    let outcome = match client.bulk(index, &operations).await {
+        Ok(status) => FlushOutcome::Flushed(status),
+        Err(err) => FlushOutcome::Failed(err),
+    };
+
+    // If nobody is listening for outcomes anymore, that's fine; keep indexing
+    let _ = outcome_tx.send(outcome);
+}
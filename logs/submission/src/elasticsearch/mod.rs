@@ -1,8 +1,14 @@
 //! Convenience wrappers around Elasticsearch
 
+pub mod alias;
+pub mod analyze;
 pub mod api_bindings;
+pub mod bulk_retry;
+pub mod indexer;
+pub mod query;
 
 use crate::config::Configuration;
+use crate::elasticsearch::query::Query;
 use anyhow::Context as _;
 use bytes::Bytes;
 use elasticsearch::auth::Credentials;
@@ -11,7 +17,8 @@ use elasticsearch::http::transport::{SingleNodeConnectionPool, TransportBuilder}
 use elasticsearch::http::{Method, Url};
 use elasticsearch::indices::IndicesCreateParts;
 use elasticsearch::{BulkParts, Elasticsearch, Error as LibError};
-use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 use slog::Logger;
 use std::iter::IntoIterator;
 
@@ -144,6 +151,7 @@ impl Client {
 
 #[derive(Clone, Debug)]
 pub struct BulkOperation {
+    id: String,
     action: Bytes,
     source: Option<Bytes>,
 }
@@ -163,18 +171,63 @@ impl BulkOperation {
         document: impl Serialize,
     ) -> Result<Self, MakeBulkOperationError> {
         let id = id.into();
+        Self::with_source(id.clone(), serde_json::json!({"create": {"_id": id }}), document)
+    }
 
-        // Create the "operation" JSON line using the ID
-        let operation_json_value = serde_json::json!({"create": {"_id": id }});
-        let action_buf = match serde_json::to_vec(&operation_json_value) {
-            Ok(vec) => Bytes::from(vec),
-            Err(err) => {
-                return Err(MakeBulkOperationError::ActionSerializationFailure(err));
-            }
-        };
+    /// Tries to create an index bulk operation instance for the given document/id,
+    /// creating the document if it doesn't exist or fully replacing it if it does
+    pub fn index(
+        id: impl Into<String>,
+        document: impl Serialize,
+    ) -> Result<Self, MakeBulkOperationError> {
+        let id = id.into();
+        Self::with_source(id.clone(), serde_json::json!({"index": {"_id": id }}), document)
+    }
+
+    /// Tries to create an update bulk operation instance,
+    /// partially updating the document with the given id
+    /// by merging in the fields present in `partial_document`
+    pub fn update(
+        id: impl Into<String>,
+        partial_document: impl Serialize,
+    ) -> Result<Self, MakeBulkOperationError> {
+        let id = id.into();
+        Self::with_source(
+            id.clone(),
+            serde_json::json!({"update": {"_id": id }}),
+            serde_json::json!({ "doc": partial_document }),
+        )
+    }
+
+    /// Creates a delete bulk operation instance for the given id.
+    /// Unlike `create`/`index`/`update`, this operation has no source line.
+    pub fn delete(id: impl Into<String>) -> Result<Self, MakeBulkOperationError> {
+        let id = id.into();
+        let action_buf = Self::serialize_action(serde_json::json!({"delete": {"_id": id }}))?;
+
+        Ok(Self {
+            id,
+            action: action_buf,
+            source: None,
+        })
+    }
+
+    /// The document id this operation applies to
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Shared helper that serializes an action line and a source line
+    fn with_source(
+        id: String,
+        action: serde_json::Value,
+        source: impl Serialize,
+    ) -> Result<Self, MakeBulkOperationError> {
+        let action_buf = Self::serialize_action(action)?;
 
         // Create the document JSON line
-        let source_buf = match serde_json::to_vec(&document) {
+        let source_buf = match serde_json::to_vec(&source) {
             Ok(vec) => Bytes::from(vec),
             Err(err) => {
                 return Err(MakeBulkOperationError::SourceSerializationFailure(err));
@@ -182,10 +235,26 @@ impl BulkOperation {
         };
 
         Ok(Self {
+            id,
             action: action_buf,
             source: Some(source_buf),
         })
     }
+
+    /// Shared helper that serializes the "operation" JSON line
+    fn serialize_action(action: serde_json::Value) -> Result<Bytes, MakeBulkOperationError> {
+        match serde_json::to_vec(&action) {
+            Ok(vec) => Ok(Bytes::from(vec)),
+            Err(err) => Err(MakeBulkOperationError::ActionSerializationFailure(err)),
+        }
+    }
+
+    /// The serialized size in bytes of this operation's action line
+    /// plus its source line (if present), used to bound buffer sizes
+    #[must_use]
+    pub fn serialized_len(&self) -> usize {
+        self.action.len() + self.source.as_ref().map_or(0, Bytes::len)
+    }
 }
 
 /// Convenience wrapper around `api_bindings::bulk::Response`
@@ -216,6 +285,16 @@ impl BulkItem {
             | Self::Update(api_bindings::bulk::ResultItemAction { ref id, .. }) => id,
         }
     }
+
+    /// Extracts the inner per-item action result from a `BulkItem` instance.
+    pub const fn action(&self) -> &api_bindings::bulk::ResultItemAction {
+        match self {
+            Self::Create(ref action)
+            | Self::Delete(ref action)
+            | Self::Index(ref action)
+            | Self::Update(ref action) => action,
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -317,3 +396,116 @@ impl Client {
         }
     }
 }
+
+/// A single matched document, carrying its Elasticsearch metadata
+/// alongside the caller's deserialized document type
+#[derive(Clone, Debug, Deserialize)]
+pub struct Hit<T> {
+    #[serde(rename = "_id")]
+    pub id: String,
+    #[serde(rename = "_score")]
+    pub score: Option<f64>,
+    #[serde(rename = "_source")]
+    pub source: T,
+}
+
+/// Convenience wrapper around the `_search` response body,
+/// deserializing `_source` documents into the caller-supplied type `T`
+#[derive(Clone, Debug)]
+pub struct SearchResults<T> {
+    pub took: i64,
+    pub total: i64,
+    pub max_score: Option<f64>,
+    pub hits: Vec<Hit<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSearchResponse<T> {
+    took: i64,
+    hits: RawHits<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawHits<T> {
+    total: RawTotal,
+    max_score: Option<f64>,
+    hits: Vec<Hit<T>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTotal {
+    value: i64,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SearchError {
+    #[error("serializing the search query body failed")]
+    BodySerializationFailure(#[source] serde_json::Error),
+    #[error("submitting the search request failed")]
+    Failure(#[source] LibError),
+    #[error("search request failed with a non-success status code {0}")]
+    ErrorStatusCode(StatusCode),
+    #[error("failed to decode response body from elasticsearch")]
+    FailedToDecode(#[source] LibError),
+}
+
+impl Client {
+    /// Submits a query to the `_search` endpoint of the given index,
+    /// deserializing each hit's `_source` into the caller-supplied type `T`.
+    ///
+    /// `size` caps the number of hits returned (Elasticsearch defaults to 10
+    /// if left unset) and `from` offsets into the matched hits, so callers
+    /// can page through `SearchResults::total` rather than silently only
+    /// ever seeing the first page.
+    pub async fn search<T>(
+        &self,
+        index: impl AsRef<str>,
+        query: Query,
+        size: Option<u64>,
+        from: Option<u64>,
+    ) -> Result<SearchResults<T>, SearchError>
+    where
+        T: DeserializeOwned,
+    {
+        let index_ref = index.as_ref();
+        let mut body = query::SearchBody::new(query);
+        if let Some(size) = size {
+            body = body.size(size);
+        }
+        if let Some(from) = from {
+            body = body.from(from);
+        }
+        let body_bytes =
+            Bytes::from(serde_json::to_vec(&body).map_err(SearchError::BodySerializationFailure)?);
+
+        // Use the untyped send API, as with `ensure_index_exists`,
+        // so that the hand-rolled query DSL types can be used directly
+        let search_future = self.inner.send(
+            Method::Post,
+            &format!("/{}/_search", index_ref),
+            HeaderMap::new(),
+            Option::<&serde_json::Value>::None,
+            Some(body_bytes),
+            None,
+        );
+
+        let response = search_future.await.map_err(SearchError::Failure)?;
+
+        let status_code = response.status_code();
+        if !status_code.is_success() {
+            return Err(SearchError::ErrorStatusCode(status_code));
+        }
+
+        let raw: RawSearchResponse<T> = response
+            .json()
+            .await
+            .map_err(SearchError::FailedToDecode)?;
+
+        Ok(SearchResults {
+            took: raw.took,
+            total: raw.hits.total.value,
+            max_score: raw.hits.max_score,
+            hits: raw.hits.hits,
+        })
+    }
+}
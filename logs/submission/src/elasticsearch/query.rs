@@ -0,0 +1,259 @@
+//! Serde-serializable Elasticsearch query DSL fragments,
+//! modeled after rs-es's `query` module.
+//!
+//! These types only need to support being *built* and serialized;
+//! they are fed into `Client::search` as the `query` clause of a search body
+//! and never need to be deserialized back out.
+
+use serde::Serialize;
+
+/// A single query clause that can be placed anywhere a query is expected
+/// (a top-level search query, or nested inside a `bool` compound query).
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Query {
+    Term(TermQuery),
+    Match(MatchQuery),
+    Range(RangeQuery),
+    Bool(Box<BoolQuery>),
+    MatchAll(MatchAllQuery),
+}
+
+impl Query {
+    /// Builds a `term` query matching an exact value in the given field
+    pub fn term(field: impl Into<String>, value: impl Serialize) -> Self {
+        Self::Term(TermQuery {
+            field: field.into(),
+            value: serde_json::json!(value),
+        })
+    }
+
+    /// Builds a `match` query, analyzing the given text against a field
+    pub fn match_query(field: impl Into<String>, query: impl Into<String>) -> Self {
+        Self::Match(MatchQuery {
+            field: field.into(),
+            query: query.into(),
+        })
+    }
+
+    /// Builds a `range` query over the given field
+    pub fn range(field: impl Into<String>) -> RangeQueryBuilder {
+        RangeQueryBuilder {
+            field: field.into(),
+            bounds: RangeBounds::default(),
+        }
+    }
+
+    /// Starts an empty `bool` compound query builder, whose clause vectors
+    /// are filled in through its `must`/`should`/`must_not`/`filter` methods
+    #[must_use]
+    pub fn bool_query() -> BoolQueryBuilder {
+        BoolQueryBuilder::default()
+    }
+
+    /// Builds a query that matches all documents
+    #[must_use]
+    pub const fn match_all() -> Self {
+        Self::MatchAll(MatchAllQuery {})
+    }
+}
+
+/// A `term` query, matching documents containing an exact term in a field
+#[derive(Clone, Debug)]
+pub struct TermQuery {
+    field: String,
+    value: serde_json::Value,
+}
+
+// Custom serialization so that the field name becomes the JSON key,
+// e.g. `{"term": {"user.id": "kimchy"}}`
+impl Serialize for TermQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.field, &self.value)?;
+        map.end()
+    }
+}
+
+/// A `match` query, full-text matching a field against an analyzed query string
+#[derive(Clone, Debug)]
+pub struct MatchQuery {
+    field: String,
+    query: String,
+}
+
+impl Serialize for MatchQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.field, &self.query)?;
+        map.end()
+    }
+}
+
+/// A `range` query, matching documents with a field value within bounds
+#[derive(Clone, Debug)]
+pub struct RangeQuery {
+    field: String,
+    bounds: RangeBounds,
+}
+
+impl Serialize for RangeQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+        let mut map = serializer.serialize_map(Some(1))?;
+        map.serialize_entry(&self.field, &self.bounds)?;
+        map.end()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct RangeBounds {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gte: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gt: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lte: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lt: Option<serde_json::Value>,
+}
+
+/// Builder for a `range` query, accumulating bounds before producing a `Query`
+pub struct RangeQueryBuilder {
+    field: String,
+    bounds: RangeBounds,
+}
+
+impl RangeQueryBuilder {
+    #[must_use]
+    pub fn gte(mut self, value: impl Serialize) -> Self {
+        self.bounds.gte = Some(serde_json::json!(value));
+        self
+    }
+
+    #[must_use]
+    pub fn gt(mut self, value: impl Serialize) -> Self {
+        self.bounds.gt = Some(serde_json::json!(value));
+        self
+    }
+
+    #[must_use]
+    pub fn lte(mut self, value: impl Serialize) -> Self {
+        self.bounds.lte = Some(serde_json::json!(value));
+        self
+    }
+
+    #[must_use]
+    pub fn lt(mut self, value: impl Serialize) -> Self {
+        self.bounds.lt = Some(serde_json::json!(value));
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Query {
+        Query::Range(RangeQuery {
+            field: self.field,
+            bounds: self.bounds,
+        })
+    }
+}
+
+/// A `bool` compound query, combining other queries with boolean logic
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct BoolQuery {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    must: Vec<Query>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    should: Vec<Query>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    must_not: Vec<Query>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    filter: Vec<Query>,
+}
+
+/// Builder for a `bool` compound query
+#[derive(Default)]
+pub struct BoolQueryBuilder {
+    inner: BoolQuery,
+}
+
+impl BoolQueryBuilder {
+    #[must_use]
+    pub fn must(mut self, clauses: impl IntoIterator<Item = Query>) -> Self {
+        self.inner.must.extend(clauses);
+        self
+    }
+
+    #[must_use]
+    pub fn should(mut self, clauses: impl IntoIterator<Item = Query>) -> Self {
+        self.inner.should.extend(clauses);
+        self
+    }
+
+    #[must_use]
+    pub fn must_not(mut self, clauses: impl IntoIterator<Item = Query>) -> Self {
+        self.inner.must_not.extend(clauses);
+        self
+    }
+
+    #[must_use]
+    pub fn filter(mut self, clauses: impl IntoIterator<Item = Query>) -> Self {
+        self.inner.filter.extend(clauses);
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Query {
+        Query::Bool(Box::new(self.inner))
+    }
+}
+
+/// A `match_all` query, matching every document
+#[derive(Clone, Debug, Serialize)]
+pub struct MatchAllQuery {}
+
+/// The full body of a `_search` request
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchBody {
+    pub query: Query,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+}
+
+impl SearchBody {
+    #[must_use]
+    pub fn new(query: Query) -> Self {
+        Self {
+            query,
+            size: None,
+            from: None,
+        }
+    }
+
+    /// Caps the number of hits returned, overriding Elasticsearch's default of 10
+    #[must_use]
+    pub const fn size(mut self, size: u64) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    /// Offsets into the matched hits, for paging past the first `size` of them
+    #[must_use]
+    pub const fn from(mut self, from: u64) -> Self {
+        self.from = Some(from);
+        self
+    }
+}